@@ -3,6 +3,9 @@ mod wasm;
 #[cfg(feature = "wasm")]
 pub use wasm::{set_panic_hook, GraphGeneratorWasm};
 
+mod store;
+pub use store::{GraphStore, MemoryStore};
+
 #[cfg(test)]
 mod tests;
 
@@ -10,54 +13,45 @@ use {
     crate::{
         lang,
         types::{
-            graph::{File, GlobalPosition, Graph, Relation, RelationKind, Symbol},
+            graph::{
+                File, FileGraph, FileRelation, GlobalPosition, Graph, Relation, RelationKind,
+                Symbol,
+            },
             lsp::{
                 CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall,
-                DocumentSymbol, Location, Position, SymbolKind,
+                DocumentSymbol, Location, Position, Range, SymbolKind,
             },
         },
     },
     std::{
         cell::RefCell,
-        collections::{hash_map::Entry, HashMap, HashSet},
+        collections::{HashMap, HashSet},
     },
 };
 
 pub struct GraphGenerator {
     lang: Box<dyn lang::Language>,
 
-    file_id_map: HashMap<String, u32>,
-    files: HashMap<String, Vec<DocumentSymbol>>,
-    incoming_calls: HashMap<GlobalPosition, Vec<CallHierarchyIncomingCall>>,
-    outgoing_calls: HashMap<GlobalPosition, Vec<CallHierarchyOutgoingCall>>,
-    interfaces: HashMap<GlobalPosition, Vec<GlobalPosition>>,
+    store: Box<dyn store::GraphStore>,
 
     filter: bool,
 }
 
 impl GraphGenerator {
     pub fn new(lang: &str, filter: bool) -> Self {
+        Self::with_store(lang, filter, Box::<store::MemoryStore>::default())
+    }
+
+    /// Build a generator over an explicit [`GraphStore`](store::GraphStore), e.g.
+    /// the `persist`-gated on-disk backend, so the index can survive across runs.
+    pub fn with_store(lang: &str, filter: bool, store: Box<dyn store::GraphStore>) -> Self {
         Self {
             lang: lang::language_handler(lang),
-
-            file_id_map: HashMap::new(),
-            files: HashMap::new(),
-            incoming_calls: HashMap::new(),
-            outgoing_calls: HashMap::new(),
-            interfaces: HashMap::new(),
-
+            store,
             filter,
         }
     }
 
-    fn alloc_file_id(&mut self, path: String) -> u32 {
-        let len = self.file_id_map.len();
-        self.file_id_map
-            .entry(path)
-            .or_insert(len as u32 + 1)
-            .to_owned()
-    }
-
     pub fn should_filter_out_file(&self, path: &str) -> bool {
         self.lang.should_filter_out_file(path)
     }
@@ -67,27 +61,36 @@ impl GraphGenerator {
             return false;
         }
 
-        match self.files.entry(path) {
-            Entry::Vacant(entry) => {
-                let key = entry.key().clone();
-                entry.insert(symbols);
-                self.alloc_file_id(key);
-            }
-            Entry::Occupied(_) => return false,
+        self.store.insert_file(path, symbols)
+    }
+
+    /// Drop a file from the index, invalidating its symbols and every call /
+    /// interface entry anchored to it. Edges into the file from elsewhere are
+    /// pruned lazily by `gen_graph`, which only keeps edges between live symbols.
+    pub fn remove_file(&mut self, path: &str) {
+        self.store.remove_file(path);
+    }
+
+    /// Re-push a single changed file: invalidate the old entry, then re-add the
+    /// new symbols. A client can call this and `gen_graph` without re-sending the
+    /// whole project.
+    pub fn update_file(&mut self, path: String, symbols: Vec<DocumentSymbol>) -> bool {
+        if self.lang.should_filter_out_file(&path) {
+            return false;
         }
 
-        return true;
+        self.store.remove_file(&path);
+        self.store.insert_file(path, symbols)
     }
 
-    // TODO: graph database
     pub fn add_incoming_calls(
         &mut self,
         path: String,
         position: Position,
         calls: Vec<CallHierarchyIncomingCall>,
     ) {
-        let location = GlobalPosition::new(self.alloc_file_id(path), position);
-        self.incoming_calls.insert(location, calls);
+        let location = GlobalPosition::new(self.store.alloc_file_id(path), position);
+        self.store.insert_incoming_calls(location, calls);
     }
 
     pub fn add_outgoing_calls(
@@ -96,8 +99,8 @@ impl GraphGenerator {
         position: Position,
         calls: Vec<CallHierarchyOutgoingCall>,
     ) {
-        let location = GlobalPosition::new(self.alloc_file_id(path), position);
-        self.outgoing_calls.insert(location, calls);
+        let location = GlobalPosition::new(self.store.alloc_file_id(path), position);
+        self.store.insert_outgoing_calls(location, calls);
     }
 
     pub fn add_interface_implementations(
@@ -106,14 +109,17 @@ impl GraphGenerator {
         position: Position,
         locations: Vec<Location>,
     ) {
-        let location = GlobalPosition::new(self.alloc_file_id(path), position);
+        let location = GlobalPosition::new(self.store.alloc_file_id(path), position);
         let implementations = locations
             .into_iter()
             .map(|location| {
-                GlobalPosition::new(self.alloc_file_id(location.uri.path), location.range.start)
+                GlobalPosition::new(
+                    self.store.alloc_file_id(location.uri.path),
+                    location.range.start,
+                )
             })
             .collect();
-        self.interfaces.insert(location, implementations);
+        self.store.insert_interfaces(location, implementations);
     }
 
     pub fn gen_graph(&self) -> Graph {
@@ -125,7 +131,8 @@ impl GraphGenerator {
         let inserted_symbols_ref = &inserted_symbols;
 
         let incoming_calls = self
-            .incoming_calls
+            .store
+            .incoming_calls()
             .iter()
             .filter_map(|(callee, callers)| symbols.contains(&callee).then_some((callee, callers)))
             .flat_map(|(to, calls)| {
@@ -139,7 +146,7 @@ impl GraphGenerator {
                     (symbols_ref.contains(&from)
                         || inserted_symbols_ref.borrow().contains(&from)
                         || {
-                            let id = *self.file_id_map.get(&call.from.uri.path)?;
+                            let id = self.store.file_id(&call.from.uri.path)?;
                             let node = files_ref.get(id as usize - 1)? as *const File;
 
                             let updated = self.try_insert_symbol(&call.from, unsafe {
@@ -155,12 +162,14 @@ impl GraphGenerator {
                         from,
                         to: to.to_owned(),
                         kind: RelationKind::Call,
+                        recursive: false,
                     })
                 })
             });
 
         let outgoing_calls = self
-            .outgoing_calls
+            .store
+            .outgoing_calls()
             .iter()
             .filter_map(|(caller, callees)| {
                 symbols_ref.contains(&caller).then_some((caller, callees))
@@ -173,12 +182,14 @@ impl GraphGenerator {
                         from: from.to_owned(),
                         to,
                         kind: RelationKind::Call,
+                        recursive: false,
                     })
                 })
             });
 
         let implementations = self
-            .interfaces
+            .store
+            .interfaces()
             .iter()
             .filter_map(|(interface, implementations)| {
                 symbols_ref
@@ -191,6 +202,7 @@ impl GraphGenerator {
                         from: location.to_owned(),
                         to: to.to_owned(),
                         kind: RelationKind::Impl,
+                        recursive: false,
                     })
                 })
             });
@@ -200,27 +212,244 @@ impl GraphGenerator {
             .chain(implementations)
             .collect::<HashSet<_>>();
 
-        Graph {
-            files,
-            relations: edges.into_iter().collect(),
+        let mut files = files;
+        let mut relations = edges.into_iter().collect::<Vec<_>>();
+
+        // unless externals are filtered out, keep the calls that reach outside the
+        // analyzed set by collapsing each external group into a single node.
+        if !self.filter {
+            relations.extend(self.external_edges(&mut files, symbols_ref));
+        }
+
+        // mark the call edges that take part in a recursion / mutual-recursion
+        // cluster, and stamp every symbol in such a cluster with a shared group
+        // id so front-ends can highlight them together.
+        let groups = self.tag_cycles(&mut relations);
+        for file in files.iter_mut() {
+            Self::annotate_cycle_groups(file.id, &mut file.symbols, &groups);
+        }
+
+        Graph { files, relations }
+    }
+
+    /// Collapse the per-symbol [`Graph`] into a file-level architecture view: every
+    /// symbol folds into its file and the symbol relations aggregate into weighted
+    /// file-to-file edges. Self-loops at file granularity are dropped.
+    pub fn gen_file_graph(&self) -> FileGraph {
+        let graph = self.gen_graph();
+
+        let mut weights: HashMap<(u32, u32, u8), usize> = HashMap::new();
+        for relation in &graph.relations {
+            let (from, to) = (relation.from.file_id, relation.to.file_id);
+            if from == to {
+                continue;
+            }
+
+            let kind = match relation.kind {
+                RelationKind::Call => 0,
+                RelationKind::Impl => 1,
+                RelationKind::Inherit => 2,
+            };
+            *weights.entry((from, to, kind)).or_insert(0) += 1;
+        }
+
+        let relations = weights
+            .into_iter()
+            .map(|((from, to, kind), weight)| FileRelation {
+                from,
+                to,
+                kind: match kind {
+                    0 => RelationKind::Call,
+                    1 => RelationKind::Impl,
+                    _ => RelationKind::Inherit,
+                },
+                weight,
+            })
+            .collect();
+
+        FileGraph {
+            files: graph.files,
+            relations,
+        }
+    }
+
+    // Synthesize aggregated nodes for calls that cross the analyzed set. Every
+    // unresolved endpoint sharing an external group (a crate, `std`, ...) collapses
+    // into one node so the graph doesn't explode, while still showing that code
+    // reaches outside the indexed files.
+    fn external_edges(
+        &self,
+        files: &mut Vec<File>,
+        symbols: &HashSet<GlobalPosition>,
+    ) -> Vec<Relation> {
+        let mut relations = Vec::new();
+        let mut seen: HashSet<(GlobalPosition, GlobalPosition)> = HashSet::new();
+        let mut group_ids: HashMap<String, u32> = HashMap::new();
+        // `remove_file` leaves ids allocated, so the id space is sparse and
+        // `files.len()` may collide with a live id. Seed past the highest id in use.
+        let mut next_id = self
+            .store
+            .file_id_map()
+            .values()
+            .chain(files.iter().map(|file| &file.id))
+            .copied()
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let zero = Position {
+            line: 0,
+            character: 0,
+        };
+
+        let mut node_for = |path: &str, files: &mut Vec<File>| -> Option<GlobalPosition> {
+            if self.store.file_id(path).is_some() {
+                return None;
+            }
+            let group = self.lang.external_group(path)?;
+            let id = *group_ids.entry(group.clone()).or_insert_with(|| {
+                let id = next_id;
+                next_id += 1;
+                files.push(File {
+                    id,
+                    path: group.clone(),
+                    symbols: vec![Symbol {
+                        name: group.clone(),
+                        kind: SymbolKind::Module,
+                        range: Range {
+                            start: zero,
+                            end: zero,
+                        },
+                        children: vec![],
+                        cycle_group: None,
+                    }],
+                });
+                id
+            });
+            Some(GlobalPosition {
+                file_id: id,
+                line: 0,
+                character: 0,
+            })
+        };
+
+        for (callee, callers) in self.store.incoming_calls().iter() {
+            if !symbols.contains(callee) {
+                continue;
+            }
+            for call in callers {
+                if let Some(from) = node_for(&call.from.uri.path, files) {
+                    if seen.insert((from, *callee)) {
+                        relations.push(Relation {
+                            from,
+                            to: *callee,
+                            kind: RelationKind::Call,
+                            recursive: false,
+                        });
+                    }
+                }
+            }
+        }
+
+        for (caller, callees) in self.store.outgoing_calls().iter() {
+            if !symbols.contains(caller) {
+                continue;
+            }
+            for call in callees {
+                if let Some(to) = node_for(&call.to.uri.path, files) {
+                    if seen.insert((*caller, to)) {
+                        relations.push(Relation {
+                            from: *caller,
+                            to,
+                            kind: RelationKind::Call,
+                            recursive: false,
+                        });
+                    }
+                }
+            }
+        }
+
+        relations
+    }
+
+    // Find the call cycles among `relations` via Tarjan's SCC algorithm, flag the
+    // participating `Call` edges as `recursive`, and return a map from each symbol
+    // in a cycle to its group id. `Impl` edges are not call edges and are ignored.
+    fn tag_cycles(&self, relations: &mut [Relation]) -> HashMap<GlobalPosition, u32> {
+        let mut adjacency: HashMap<GlobalPosition, Vec<GlobalPosition>> = HashMap::new();
+        for rel in relations.iter() {
+            if matches!(rel.kind, RelationKind::Call) {
+                adjacency.entry(rel.from).or_default().push(rel.to);
+            }
+        }
+
+        let sccs = tarjan_scc(&adjacency);
+
+        // a component is a cycle when it has more than one member, or when it is a
+        // single node with a self-edge.
+        let self_edges = adjacency
+            .iter()
+            .filter(|(from, tos)| tos.contains(from))
+            .map(|(from, _)| *from)
+            .collect::<HashSet<_>>();
+
+        let mut group_of = HashMap::new();
+        let mut next_group = 0u32;
+        for scc in sccs {
+            if scc.len() < 2 && !self_edges.contains(&scc[0]) {
+                continue;
+            }
+            let group = next_group;
+            next_group += 1;
+            for node in scc {
+                group_of.insert(node, group);
+            }
+        }
+
+        for rel in relations.iter_mut() {
+            if matches!(rel.kind, RelationKind::Call)
+                && group_of.get(&rel.from) == group_of.get(&rel.to)
+                && group_of.contains_key(&rel.from)
+            {
+                rel.recursive = true;
+            }
+        }
+
+        group_of
+    }
+
+    fn annotate_cycle_groups(
+        file_id: u32,
+        symbols: &mut [Symbol],
+        groups: &HashMap<GlobalPosition, u32>,
+    ) {
+        for symbol in symbols.iter_mut() {
+            let position = GlobalPosition {
+                file_id,
+                line: symbol.range.start.line,
+                character: symbol.range.start.character,
+            };
+            symbol.cycle_group = groups.get(&position).copied();
+            Self::annotate_cycle_groups(file_id, &mut symbol.children, groups);
         }
     }
 
     fn collect_files_and_symbols(&self) -> (Vec<File>, HashSet<GlobalPosition>) {
         let mut all_symbols = HashSet::new();
+        let file_id_map = self.store.file_id_map();
         let files = self
-            .files
+            .store
+            .files()
             .iter()
             .map(|(p, symbols)| {
                 let symbols = symbols
                     .iter()
                     .filter_map(|s| {
-                        self.convert_symbol(self.file_id_map[p], s, None, &mut all_symbols)
+                        self.convert_symbol(file_id_map[p], s, None, &mut all_symbols)
                     })
                     .collect();
 
                 File {
-                    id: self.file_id_map[p],
+                    id: file_id_map[p],
                     path: p.clone(),
                     symbols,
                 }
@@ -254,6 +483,7 @@ impl GraphGenerator {
             kind: symbol.kind,
             name: symbol.name.clone(),
             children,
+            cycle_group: None,
         })
     }
 
@@ -303,6 +533,7 @@ impl GraphGenerator {
                         kind: item.kind,
                         range: item.selection_range,
                         children,
+                        cycle_group: None,
                     },
                 );
             }
@@ -313,8 +544,84 @@ impl GraphGenerator {
 
     fn call_item_global_location(&self, item: &CallHierarchyItem) -> Option<GlobalPosition> {
         Some(GlobalPosition::new(
-            *self.file_id_map.get(&item.uri.path)?,
+            self.store.file_id(&item.uri.path)?,
             item.selection_range.start,
         ))
     }
 }
+
+// Iterative Tarjan's strongly-connected-components. Recursion is avoided on
+// purpose: this runs in wasm, where a deeply nested call graph would otherwise
+// overflow the stack.
+fn tarjan_scc(
+    adjacency: &HashMap<GlobalPosition, Vec<GlobalPosition>>,
+) -> Vec<Vec<GlobalPosition>> {
+    let mut nodes = adjacency.keys().copied().collect::<HashSet<_>>();
+    for tos in adjacency.values() {
+        nodes.extend(tos.iter().copied());
+    }
+
+    let empty: Vec<GlobalPosition> = Vec::new();
+
+    let mut index_of: HashMap<GlobalPosition, u32> = HashMap::new();
+    let mut lowlink: HashMap<GlobalPosition, u32> = HashMap::new();
+    let mut on_stack: HashSet<GlobalPosition> = HashSet::new();
+    let mut stack: Vec<GlobalPosition> = Vec::new();
+    let mut sccs: Vec<Vec<GlobalPosition>> = Vec::new();
+    let mut next_index = 0u32;
+
+    // explicit work stack of (node, index of the next child to visit)
+    let mut work: Vec<(GlobalPosition, usize)> = Vec::new();
+
+    for &root in nodes.iter() {
+        if index_of.contains_key(&root) {
+            continue;
+        }
+
+        work.push((root, 0));
+        while let Some(&(v, child)) = work.last() {
+            if child == 0 {
+                index_of.insert(v, next_index);
+                lowlink.insert(v, next_index);
+                next_index += 1;
+                stack.push(v);
+                on_stack.insert(v);
+            }
+
+            let neighbours = adjacency.get(&v).unwrap_or(&empty);
+            if child < neighbours.len() {
+                work.last_mut().unwrap().1 += 1;
+
+                let w = neighbours[child];
+                if !index_of.contains_key(&w) {
+                    work.push((w, 0));
+                } else if on_stack.contains(&w) {
+                    let low = lowlink[&v].min(index_of[&w]);
+                    lowlink.insert(v, low);
+                }
+            } else {
+                // all children explored: if v roots an SCC, pop it off the stack
+                if lowlink[&v] == index_of[&v] {
+                    let mut scc = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack.remove(&w);
+                        scc.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    sccs.push(scc);
+                }
+
+                work.pop();
+                if let Some(&(parent, _)) = work.last() {
+                    let low = lowlink[&parent].min(lowlink[&v]);
+                    lowlink.insert(parent, low);
+                }
+            }
+        }
+    }
+
+    sccs
+}
@@ -0,0 +1,122 @@
+#[cfg(feature = "persist")]
+mod persist;
+#[cfg(feature = "persist")]
+pub use persist::RedbStore;
+
+use crate::types::{
+    graph::GlobalPosition,
+    lsp::{CallHierarchyIncomingCall, CallHierarchyOutgoingCall, DocumentSymbol},
+};
+use std::collections::{hash_map::Entry, HashMap};
+
+/// Backing storage for the symbols and relations an LSP client feeds into the
+/// [`GraphGenerator`](super::GraphGenerator).
+///
+/// Abstracting the four maps behind a trait lets the generator act as an
+/// incremental index: a client can push or invalidate individual files without
+/// re-sending the whole project. The default [`MemoryStore`] keeps everything in
+/// memory; the `persist` feature adds an on-disk [`RedbStore`].
+pub trait GraphStore {
+    fn file_id(&self, path: &str) -> Option<u32>;
+
+    /// Allocate (or return the existing) stable id for `path`. Ids are handed out
+    /// monotonically and kept across runs so edge endpoints stay valid even when
+    /// only some files are re-pushed.
+    fn alloc_file_id(&mut self, path: String) -> u32;
+
+    /// Insert a file's symbols. Returns `false` when the file is already present.
+    fn insert_file(&mut self, path: String, symbols: Vec<DocumentSymbol>) -> bool;
+
+    /// Drop a file's symbols together with every call/interface entry anchored to
+    /// it. The file keeps its allocated id so surviving edges remain resolvable.
+    fn remove_file(&mut self, path: &str);
+
+    fn insert_incoming_calls(&mut self, at: GlobalPosition, calls: Vec<CallHierarchyIncomingCall>);
+    fn insert_outgoing_calls(&mut self, at: GlobalPosition, calls: Vec<CallHierarchyOutgoingCall>);
+    fn insert_interfaces(&mut self, at: GlobalPosition, implementations: Vec<GlobalPosition>);
+
+    fn file_id_map(&self) -> &HashMap<String, u32>;
+    fn files(&self) -> &HashMap<String, Vec<DocumentSymbol>>;
+    fn incoming_calls(&self) -> &HashMap<GlobalPosition, Vec<CallHierarchyIncomingCall>>;
+    fn outgoing_calls(&self) -> &HashMap<GlobalPosition, Vec<CallHierarchyOutgoingCall>>;
+    fn interfaces(&self) -> &HashMap<GlobalPosition, Vec<GlobalPosition>>;
+}
+
+#[derive(Default)]
+pub struct MemoryStore {
+    file_id_map: HashMap<String, u32>,
+    files: HashMap<String, Vec<DocumentSymbol>>,
+    incoming_calls: HashMap<GlobalPosition, Vec<CallHierarchyIncomingCall>>,
+    outgoing_calls: HashMap<GlobalPosition, Vec<CallHierarchyOutgoingCall>>,
+    interfaces: HashMap<GlobalPosition, Vec<GlobalPosition>>,
+}
+
+impl GraphStore for MemoryStore {
+    fn file_id(&self, path: &str) -> Option<u32> {
+        self.file_id_map.get(path).copied()
+    }
+
+    fn alloc_file_id(&mut self, path: String) -> u32 {
+        let len = self.file_id_map.len();
+        self.file_id_map
+            .entry(path)
+            .or_insert(len as u32 + 1)
+            .to_owned()
+    }
+
+    fn insert_file(&mut self, path: String, symbols: Vec<DocumentSymbol>) -> bool {
+        match self.files.entry(path) {
+            Entry::Vacant(entry) => {
+                let key = entry.key().clone();
+                entry.insert(symbols);
+                self.alloc_file_id(key);
+                true
+            }
+            Entry::Occupied(_) => false,
+        }
+    }
+
+    fn remove_file(&mut self, path: &str) {
+        self.files.remove(path);
+
+        let Some(file_id) = self.file_id_map.get(path).copied() else {
+            return;
+        };
+
+        self.incoming_calls.retain(|at, _| at.file_id != file_id);
+        self.outgoing_calls.retain(|at, _| at.file_id != file_id);
+        self.interfaces.retain(|at, _| at.file_id != file_id);
+    }
+
+    fn insert_incoming_calls(&mut self, at: GlobalPosition, calls: Vec<CallHierarchyIncomingCall>) {
+        self.incoming_calls.insert(at, calls);
+    }
+
+    fn insert_outgoing_calls(&mut self, at: GlobalPosition, calls: Vec<CallHierarchyOutgoingCall>) {
+        self.outgoing_calls.insert(at, calls);
+    }
+
+    fn insert_interfaces(&mut self, at: GlobalPosition, implementations: Vec<GlobalPosition>) {
+        self.interfaces.insert(at, implementations);
+    }
+
+    fn file_id_map(&self) -> &HashMap<String, u32> {
+        &self.file_id_map
+    }
+
+    fn files(&self) -> &HashMap<String, Vec<DocumentSymbol>> {
+        &self.files
+    }
+
+    fn incoming_calls(&self) -> &HashMap<GlobalPosition, Vec<CallHierarchyIncomingCall>> {
+        &self.incoming_calls
+    }
+
+    fn outgoing_calls(&self) -> &HashMap<GlobalPosition, Vec<CallHierarchyOutgoingCall>> {
+        &self.outgoing_calls
+    }
+
+    fn interfaces(&self) -> &HashMap<GlobalPosition, Vec<GlobalPosition>> {
+        &self.interfaces
+    }
+}
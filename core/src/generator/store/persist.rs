@@ -0,0 +1,155 @@
+use {
+    super::{GraphStore, MemoryStore},
+    crate::types::{
+        graph::GlobalPosition,
+        lsp::{CallHierarchyIncomingCall, CallHierarchyOutgoingCall, DocumentSymbol},
+    },
+    redb::{Database, ReadableTable, TableDefinition},
+    std::{collections::HashMap, path::Path},
+};
+
+// A single table keyed by a logical entry name; values are serde_json blobs so
+// that the schema can evolve without a migration. Keeping one table keeps the
+// on-disk layout trivial — the hot data still lives in the wrapped `MemoryStore`.
+const STORE: TableDefinition<&str, &[u8]> = TableDefinition::new("graph_store");
+
+const FILE_IDS_KEY: &str = "file_ids";
+const FILES_KEY: &str = "files";
+const INCOMING_KEY: &str = "incoming";
+const OUTGOING_KEY: &str = "outgoing";
+const INTERFACES_KEY: &str = "interfaces";
+
+/// On-disk [`GraphStore`] backed by [`redb`]. The whole index is hydrated into a
+/// [`MemoryStore`] on [`open`](RedbStore::open) and written back on
+/// [`commit`](RedbStore::commit), so reads stay as cheap as the in-memory path
+/// while surviving across runs.
+pub struct RedbStore {
+    db: Database,
+    cache: MemoryStore,
+}
+
+impl RedbStore {
+    pub fn open(path: &Path) -> Result<Self, redb::Error> {
+        let db = Database::create(path)?;
+        let cache = Self::hydrate(&db)?;
+        Ok(Self { db, cache })
+    }
+
+    fn hydrate(db: &Database) -> Result<MemoryStore, redb::Error> {
+        let mut store = MemoryStore::default();
+
+        let txn = db.begin_read()?;
+        let table = match txn.open_table(STORE) {
+            Ok(table) => table,
+            Err(redb::TableError::TableDoesNotExist(_)) => return Ok(store),
+            Err(err) => return Err(err.into()),
+        };
+
+        let load = |key: &str| -> Option<Vec<u8>> {
+            table.get(key).ok().flatten().map(|v| v.value().to_vec())
+        };
+
+        if let Some(bytes) = load(FILE_IDS_KEY) {
+            // restore the persisted ids as-is so edge endpoints keep resolving to
+            // the same files; ids are dense, so later allocations stay fresh.
+            store.file_id_map = serde_json::from_slice(&bytes).unwrap_or_default();
+        }
+        if let Some(bytes) = load(FILES_KEY) {
+            let files: HashMap<String, Vec<DocumentSymbol>> =
+                serde_json::from_slice(&bytes).unwrap_or_default();
+            for (path, symbols) in files {
+                store.insert_file(path, symbols);
+            }
+        }
+        if let Some(bytes) = load(INCOMING_KEY) {
+            let calls: HashMap<GlobalPosition, Vec<CallHierarchyIncomingCall>> =
+                serde_json::from_slice(&bytes).unwrap_or_default();
+            for (at, calls) in calls {
+                store.insert_incoming_calls(at, calls);
+            }
+        }
+        if let Some(bytes) = load(OUTGOING_KEY) {
+            let calls: HashMap<GlobalPosition, Vec<CallHierarchyOutgoingCall>> =
+                serde_json::from_slice(&bytes).unwrap_or_default();
+            for (at, calls) in calls {
+                store.insert_outgoing_calls(at, calls);
+            }
+        }
+        if let Some(bytes) = load(INTERFACES_KEY) {
+            let interfaces: HashMap<GlobalPosition, Vec<GlobalPosition>> =
+                serde_json::from_slice(&bytes).unwrap_or_default();
+            for (at, implementations) in interfaces {
+                store.insert_interfaces(at, implementations);
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Flush the current state to disk.
+    pub fn commit(&self) -> Result<(), redb::Error> {
+        let txn = self.db.begin_write()?;
+        {
+            let mut table = txn.open_table(STORE)?;
+            let mut put = |key: &str, value: &[u8]| table.insert(key, value).map(|_| ());
+
+            put(FILE_IDS_KEY, &serde_json::to_vec(self.cache.file_id_map()).unwrap())?;
+            put(FILES_KEY, &serde_json::to_vec(self.cache.files()).unwrap())?;
+            put(INCOMING_KEY, &serde_json::to_vec(self.cache.incoming_calls()).unwrap())?;
+            put(OUTGOING_KEY, &serde_json::to_vec(self.cache.outgoing_calls()).unwrap())?;
+            put(INTERFACES_KEY, &serde_json::to_vec(self.cache.interfaces()).unwrap())?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+}
+
+impl GraphStore for RedbStore {
+    fn file_id(&self, path: &str) -> Option<u32> {
+        self.cache.file_id(path)
+    }
+
+    fn alloc_file_id(&mut self, path: String) -> u32 {
+        self.cache.alloc_file_id(path)
+    }
+
+    fn insert_file(&mut self, path: String, symbols: Vec<DocumentSymbol>) -> bool {
+        self.cache.insert_file(path, symbols)
+    }
+
+    fn remove_file(&mut self, path: &str) {
+        self.cache.remove_file(path)
+    }
+
+    fn insert_incoming_calls(&mut self, at: GlobalPosition, calls: Vec<CallHierarchyIncomingCall>) {
+        self.cache.insert_incoming_calls(at, calls)
+    }
+
+    fn insert_outgoing_calls(&mut self, at: GlobalPosition, calls: Vec<CallHierarchyOutgoingCall>) {
+        self.cache.insert_outgoing_calls(at, calls)
+    }
+
+    fn insert_interfaces(&mut self, at: GlobalPosition, implementations: Vec<GlobalPosition>) {
+        self.cache.insert_interfaces(at, implementations)
+    }
+
+    fn file_id_map(&self) -> &HashMap<String, u32> {
+        self.cache.file_id_map()
+    }
+
+    fn files(&self) -> &HashMap<String, Vec<DocumentSymbol>> {
+        self.cache.files()
+    }
+
+    fn incoming_calls(&self) -> &HashMap<GlobalPosition, Vec<CallHierarchyIncomingCall>> {
+        self.cache.incoming_calls()
+    }
+
+    fn outgoing_calls(&self) -> &HashMap<GlobalPosition, Vec<CallHierarchyOutgoingCall>> {
+        self.cache.outgoing_calls()
+    }
+
+    fn interfaces(&self) -> &HashMap<GlobalPosition, Vec<GlobalPosition>> {
+        self.cache.interfaces()
+    }
+}
@@ -1,12 +1,16 @@
 use {
-    super::GraphGenerator,
-    crate::types::lsp::{DocumentSymbol, Position, Range, SymbolKind},
+    super::{tarjan_scc, GraphGenerator},
+    crate::types::{
+        graph::GlobalPosition,
+        lsp::{DocumentSymbol, Position, Range, SymbolKind},
+    },
+    std::collections::HashMap,
 };
 
 #[test]
 #[allow(deprecated)]
 fn nested_function() {
-    let mut generator = GraphGenerator::new("");
+    let mut generator = GraphGenerator::new("", true);
     let parent_range = Range {
         start: Position {
             line: 1,
@@ -52,3 +56,36 @@ fn nested_function() {
     let dot = generator.gen_graph();
     println!("{:#?}", dot);
 }
+
+fn pos(file_id: u32, line: u32) -> GlobalPosition {
+    GlobalPosition {
+        file_id,
+        line,
+        character: 0,
+    }
+}
+
+#[test]
+fn tarjan_detects_cycles_and_keeps_self_edges_singleton() {
+    let (a, b, c) = (pos(1, 1), pos(1, 2), pos(1, 3));
+    let (d, e, f) = (pos(2, 1), pos(2, 2), pos(3, 1));
+
+    let mut adjacency = HashMap::new();
+    adjacency.insert(a, vec![b]);
+    adjacency.insert(b, vec![c]);
+    adjacency.insert(c, vec![a]); // a -> b -> c -> a, a single 3-node cycle
+    adjacency.insert(d, vec![e]); // acyclic
+    adjacency.insert(f, vec![f]); // self-edge
+
+    let sccs = tarjan_scc(&adjacency);
+
+    let cycle = sccs.iter().find(|scc| scc.len() > 1).unwrap();
+    assert_eq!(cycle.len(), 3);
+    assert!([a, b, c].iter().all(|node| cycle.contains(node)));
+
+    // the acyclic edge and its endpoints stay singleton components
+    assert!(sccs.iter().any(|scc| scc.as_slice() == [d]));
+    assert!(sccs.iter().any(|scc| scc.as_slice() == [e]));
+    // a self-edge is its own singleton component, reported separately
+    assert!(sccs.iter().any(|scc| scc.as_slice() == [f]));
+}
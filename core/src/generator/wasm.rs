@@ -51,6 +51,16 @@ impl GraphGeneratorWasm {
         self.inner.borrow_mut().add_file(path, symbols)
     }
 
+    pub fn remove_file(&self, path: String) {
+        self.inner.borrow_mut().remove_file(&path);
+    }
+
+    pub fn update_file(&self, path: String, symbols: JsValue) -> bool {
+        let symbols = serde_wasm_bindgen::from_value::<Vec<DocumentSymbol>>(symbols).unwrap();
+
+        self.inner.borrow_mut().update_file(path, symbols)
+    }
+
     pub fn add_incoming_calls(&self, path: String, position: JsValue, calls: JsValue) {
         let position = serde_wasm_bindgen::from_value::<Position>(position).unwrap();
         let calls =
@@ -88,4 +98,8 @@ impl GraphGeneratorWasm {
     pub fn gen_graph(&self) -> JsValue {
         serde_wasm_bindgen::to_value(&self.inner.borrow().gen_graph()).unwrap()
     }
+
+    pub fn gen_file_graph(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.inner.borrow().gen_file_graph()).unwrap()
+    }
 }
@@ -12,6 +12,28 @@ pub(crate) trait Language {
         false
     }
 
+    // Map an unknown file path to a coarse external group label — the crate name
+    // for a cargo `registry/src/<index>/<crate>-<version>` path, or the toolchain
+    // crate (`std`/`core`/`alloc`) for rust library sources. Returns `None` when
+    // the path shouldn't be collapsed into an external node.
+    fn external_group(&self, path: &str) -> Option<String> {
+        if let Some(rest) = path.split("/registry/src/").nth(1) {
+            let krate = rest.split('/').nth(1)?;
+            let name = krate.rsplit_once('-').map_or(krate, |(name, _)| name);
+            return Some(name.to_string());
+        }
+
+        if let Some(rest) = path.split("/library/").nth(1) {
+            if let Some(krate) = rest.split('/').next() {
+                if matches!(krate, "std" | "core" | "alloc") {
+                    return Some(krate.to_string());
+                }
+            }
+        }
+
+        None
+    }
+
     fn filter_symbol(&self, symbol: &DocumentSymbol, parent: Option<&DocumentSymbol>) -> bool {
         match symbol.kind {
             SymbolKind::Constant | SymbolKind::Variable | SymbolKind::EnumMember => false,
@@ -11,6 +11,24 @@ pub struct Graph {
     pub relations: Vec<Relation>,
 }
 
+/// A birds-eye view of [`Graph`] where every symbol node is collapsed into its
+/// containing file and the symbol-level relations are aggregated into weighted
+/// file-to-file edges.
+#[derive(Debug, Serialize)]
+pub struct FileGraph {
+    pub files: Vec<File>,
+    pub relations: Vec<FileRelation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileRelation {
+    pub from: u32,
+    pub to: u32,
+    pub kind: RelationKind,
+    // how many underlying symbol-level relations this edge aggregates.
+    pub weight: usize,
+}
+
 #[derive(Debug, Serialize)]
 pub struct File {
     pub id: u32,
@@ -24,6 +42,10 @@ pub struct Symbol {
     pub kind: SymbolKind,
     pub range: Range,
     pub children: Vec<Symbol>,
+    // id of the call cycle this symbol belongs to, if any; shared by every
+    // symbol in the same strongly-connected component.
+    #[serde(rename = "cycleGroup", skip_serializing_if = "Option::is_none")]
+    pub cycle_group: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -31,6 +53,9 @@ pub struct Relation {
     pub from: GlobalPosition,
     pub to: GlobalPosition,
     pub kind: RelationKind,
+    // true when both endpoints lie in the same call cycle (or it is a self-call).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub recursive: bool,
 }
 
 impl Hash for Relation {
@@ -8,10 +8,13 @@ mod tests;
 
 use {
     crate::{
-        graph::{dot::Dot, Cell, Edge, EdgeCssClass, Subgraph, TableNode},
+        graph::{
+            dot::Dot, mermaid::Mermaid, Cell, Edge, EdgeCssClass, Format, Renderer, Subgraph,
+            TableNode,
+        },
         lang,
         types::{
-            CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall,
+            self, CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall,
             DocumentSymbol, Location, Position, SymbolKind, SymbolLocation,
         },
     },
@@ -19,12 +22,11 @@ use {
     std::{
         cell::RefCell,
         collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
-        path::{Path, PathBuf},
+        path::{Component, PathBuf},
     },
 };
 
 pub struct GraphGenerator {
-    // TODO: use a trie map to store files
     root: String,
     next_file_id: u32,
 
@@ -34,8 +36,13 @@ pub struct GraphGenerator {
     incoming_calls: HashMap<SymbolLocation, Vec<CallHierarchyIncomingCall>>,
     outgoing_calls: HashMap<SymbolLocation, Vec<CallHierarchyOutgoingCall>>,
     interfaces: HashMap<SymbolLocation, Vec<SymbolLocation>>,
+    // supertype -> its subtypes, populated from LSP typeHierarchy results
+    hierarchy: HashMap<SymbolLocation, Vec<SymbolLocation>>,
 
     highlights: HashMap<u32, HashSet<(u32, u32)>>,
+
+    #[cfg(feature = "persist")]
+    persist: Option<(PathBuf, crate::persist::FileStore)>,
 }
 
 impl GraphGenerator {
@@ -47,9 +54,93 @@ impl GraphGenerator {
             incoming_calls: HashMap::new(),
             outgoing_calls: HashMap::new(),
             interfaces: HashMap::new(),
+            hierarchy: HashMap::new(),
             highlights: HashMap::new(),
 
             lang: lang::language_handler(lang),
+
+            #[cfg(feature = "persist")]
+            persist: None,
+        }
+    }
+
+    /// Open a generator backed by an on-disk cache at `db_path`, reusing the
+    /// entries persisted by a previous [`commit`](Self::commit). Unchanged files
+    /// can then be restored from the cache instead of being re-queried.
+    #[cfg(feature = "persist")]
+    pub fn open(root: String, lang: &str, db_path: PathBuf) -> std::io::Result<Self> {
+        let store = crate::persist::FileStore::load(&db_path)?;
+
+        let mut generator = Self::new(root, lang);
+        generator.persist = Some((db_path, store));
+        Ok(generator)
+    }
+
+    /// Whether `path` is already cached with the exact same `content`, so the
+    /// client can skip the expensive language-server queries for it.
+    #[cfg(feature = "persist")]
+    pub fn is_cached(&self, path: &str, content: &str) -> bool {
+        self.persist
+            .as_ref()
+            .is_some_and(|(_, store)| store.is_unchanged(path, content))
+    }
+
+    /// Restore a file's cached symbols, calls, and interface implementations into
+    /// the live index. Returns `false` (leaving the index untouched) when the file
+    /// isn't cached or its contents changed.
+    #[cfg(feature = "persist")]
+    pub fn restore_cached(&mut self, path: String, content: &str) -> bool {
+        let entry = match self.persist.as_ref().and_then(|(_, store)| store.get(&path, content)) {
+            Some(entry) => entry.clone(),
+            None => return false,
+        };
+
+        for (position, calls) in entry.incoming_calls {
+            self.incoming_calls
+                .insert(SymbolLocation::new(path.clone(), position), calls);
+        }
+        for (position, calls) in entry.outgoing_calls {
+            self.outgoing_calls
+                .insert(SymbolLocation::new(path.clone(), position), calls);
+        }
+        for (position, implementations) in entry.interfaces {
+            self.interfaces
+                .insert(SymbolLocation::new(path.clone(), position), implementations);
+        }
+
+        self.files.insert(path, entry.table);
+        true
+    }
+
+    /// Snapshot the current derived data for `path` into the cache, keyed by the
+    /// content-addressed digest of its `content`.
+    #[cfg(feature = "persist")]
+    pub fn cache_file(&mut self, path: String, content: &str) {
+        if self.persist.is_none() {
+            return;
+        }
+        let Some(table) = self.files.get(&path) else {
+            return;
+        };
+
+        let entry = crate::persist::Entry {
+            table: table.clone(),
+            incoming_calls: entries_for(&self.incoming_calls, &path),
+            outgoing_calls: entries_for(&self.outgoing_calls, &path),
+            interfaces: entries_for(&self.interfaces, &path),
+        };
+
+        if let Some((_, store)) = self.persist.as_mut() {
+            store.insert(path, content, entry);
+        }
+    }
+
+    /// Flush the updated cache entries to disk.
+    #[cfg(feature = "persist")]
+    pub fn commit(&self) -> std::io::Result<()> {
+        match self.persist.as_ref() {
+            Some((db_path, store)) => store.flush(db_path),
+            None => Ok(()),
         }
     }
 
@@ -80,7 +171,6 @@ impl GraphGenerator {
         return true;
     }
 
-    // TODO: graph database
     pub fn add_incoming_calls(
         &mut self,
         file_path: String,
@@ -136,13 +226,107 @@ impl GraphGenerator {
         self.interfaces.insert(location, implementations);
     }
 
+    pub fn add_supertypes(
+        &mut self,
+        file_path: String,
+        position: Position,
+        locations: Vec<Location>,
+    ) {
+        let subtype = SymbolLocation::new(file_path, position);
+        for location in locations {
+            let supertype = SymbolLocation::new(location.uri.path, location.range.start);
+            self.hierarchy
+                .entry(supertype)
+                .or_default()
+                .push(subtype.clone());
+        }
+    }
+
+    pub fn add_subtypes(&mut self, file_path: String, position: Position, locations: Vec<Location>) {
+        let supertype = SymbolLocation::new(file_path, position);
+        let subtypes = locations
+            .into_iter()
+            .map(|location| SymbolLocation::new(location.uri.path, location.range.start))
+            .collect();
+        self.hierarchy.insert(supertype, subtypes);
+    }
+
     pub fn generate_dot_source(&self) -> String {
+        self.generate(Format::Dot)
+    }
+
+    /// Serialize the graph in the requested output `format`, dispatching to the
+    /// matching [`Renderer`].
+    pub fn generate(&self, format: Format) -> String {
+        let cell_ids = self.collect_cell_ids();
+        let edges = self
+            .resolve_edges(&cell_ids)
+            .into_iter()
+            .collect::<Vec<_>>();
+        let tables = self.files.values().collect::<Vec<_>>();
+        let subgraphs = self.subgraphs(self.files.values());
+
+        match format {
+            Format::Dot => Dot.render(&tables, &edges, &subgraphs, &self.highlights),
+            Format::Mermaid => Mermaid.render(&tables, &edges, &subgraphs, &self.highlights),
+        }
+    }
+
+    /// Build a `Graph { files, relations }` from the same resolved edges as
+    /// `generate_dot_source` and serialize it to JSON, giving downstream tools a
+    /// stable machine-readable format instead of having to parse DOT.
+    pub fn generate_json(&self) -> String {
+        let cell_ids = self.collect_cell_ids();
+        let edges = self.resolve_edges(&cell_ids);
+
+        let files = self
+            .files
+            .values()
+            .map(|tbl| types::File {
+                id: tbl.id,
+                path: tbl.path.to_str().unwrap().to_string(),
+                symbols: tbl.cells.iter().map(Self::cell_symbol).collect(),
+            })
+            .collect();
+
+        let relations = edges
+            .into_iter()
+            .map(|edge| types::Relation {
+                from: types::GlobalPosition::from(edge.from),
+                to: types::GlobalPosition::from(edge.to),
+                kind: if edge.classes.contains(EdgeCssClass::Impl) {
+                    types::RelationKind::Impl
+                } else if edge.classes.contains(EdgeCssClass::Inherit) {
+                    types::RelationKind::Inherit
+                } else {
+                    types::RelationKind::Call
+                },
+            })
+            .collect();
+
+        serde_json::to_string(&types::Graph { files, relations }).unwrap()
+    }
+
+    fn cell_symbol(cell: &Cell) -> types::Symbol {
+        types::Symbol {
+            name: cell.title.clone(),
+            kind: cell.kind,
+            range: cell.range,
+            children: cell.children.iter().map(Self::cell_symbol).collect(),
+        }
+    }
+
+    fn collect_cell_ids(&self) -> HashSet<(u32, Position)> {
         let mut cell_ids = HashSet::new();
         self.files
             .iter()
             .flat_map(|(_, tbl)| tbl.cells.iter().map(|cell| (tbl.id, cell)))
-            .for_each(|(tid, cell)| self.collect_cell_ids(tid, cell, &mut cell_ids));
-        let cell_ids_ref = &cell_ids;
+            .for_each(|(tid, cell)| self.collect_cell_ids_into(tid, cell, &mut cell_ids));
+        cell_ids
+    }
+
+    fn resolve_edges(&self, cell_ids: &HashSet<(u32, Position)>) -> HashSet<Edge> {
+        let cell_ids_ref = cell_ids;
 
         let inserted_symbols = RefCell::new(HashSet::new());
         let inserted_symbols_ref = &inserted_symbols;
@@ -228,75 +412,74 @@ impl GraphGenerator {
                 })
             });
 
-        let edges = incoming_calls
-            .chain(outgoing_calls)
-            .chain(implementations)
-            .collect::<HashSet<_>>();
+        let hierarchy = self
+            .hierarchy
+            .iter()
+            .filter_map(|(supertype, subtypes)| {
+                let to = supertype.location_id(&self.files)?;
+
+                cell_ids.contains(&to).then_some((to, subtypes))
+            })
+            .flat_map(|(to, subtypes)| {
+                subtypes.into_iter().filter_map(move |location| {
+                    let from = location.location_id(&self.files)?;
 
-        let subgraphs = self.subgraphs(self.files.iter().map(|(_, f)| f));
+                    cell_ids_ref.contains(&from).then_some(Edge {
+                        from,
+                        to,
+                        classes: EdgeCssClass::Inherit.into(),
+                    })
+                })
+            });
 
-        Dot::generate_dot_source(self.files.values(), edges.into_iter(), &subgraphs)
+        incoming_calls
+            .chain(outgoing_calls)
+            .chain(implementations)
+            .chain(hierarchy)
+            .collect::<HashSet<_>>()
     }
 
     fn subgraphs<'a, I>(&'a self, files: I) -> Vec<Subgraph>
     where
         I: Iterator<Item = &'a TableNode>,
     {
-        let mut dirs = BTreeMap::new();
+        // Insert every file into a trie keyed by directory segment, then walk it
+        // once to produce the cluster hierarchy. This is linear in the number of
+        // path segments and, unlike prefix matching on strings, can't mis-nest
+        // sibling directories that share a name prefix (`src/app` vs `src/app_utils`).
+        let mut root = DirTrie::default();
         for f in files {
-            let parent = f.path.parent().unwrap();
-            dirs.entry(parent)
-                .or_insert(Vec::new())
-                .push(f.path.clone());
-        }
-
-        let mut subgraphs: Vec<Subgraph> = vec![];
-
-        dirs.iter().for_each(|(dir, files)| {
-            let nodes = files
-                .iter()
-                .map(|path| {
-                    self.files
-                        .get(path.to_str().unwrap())
-                        .unwrap()
-                        .id
-                        .to_string()
-                })
-                .collect::<Vec<_>>();
-
+            let dir = f.path.parent().unwrap();
             let dir = dir.strip_prefix(&self.root).unwrap_or(dir);
-            self.add_subgraph(dir, nodes, &mut subgraphs);
-        });
-
-        subgraphs
-    }
 
-    fn add_subgraph<'a, 'b, 'c>(
-        &'a self,
-        dir: &'b Path,
-        nodes: Vec<String>,
-        subgraphs: &'c mut Vec<Subgraph>,
-    ) {
-        let ancestor = subgraphs.iter_mut().find(|g| dir.starts_with(&g.title));
-
-        match ancestor {
-            None => subgraphs.push(Subgraph {
-                title: dir.to_str().unwrap().into(),
-                nodes,
-                subgraphs: vec![],
-            }),
-            Some(ancestor) => {
-                let dir = dir.strip_prefix(&ancestor.title).unwrap();
-                self.add_subgraph(dir, nodes, &mut ancestor.subgraphs);
+            let mut node = &mut root;
+            for component in dir.components() {
+                if let Component::Normal(segment) = component {
+                    node = node
+                        .children
+                        .entry(segment.to_str().unwrap().to_string())
+                        .or_default();
+                }
             }
+            node.nodes.push(f.id.to_string());
         }
+
+        root.children
+            .into_iter()
+            .map(|(segment, child)| child.into_subgraph(segment))
+            .collect()
     }
 
-    fn collect_cell_ids(&self, table_id: u32, cell: &Cell, ids: &mut HashSet<(u32, Position)>) {
+    fn collect_cell_ids_into(
+        &self,
+        table_id: u32,
+        cell: &Cell,
+        ids: &mut HashSet<(u32, Position)>,
+    ) {
         ids.insert((table_id, cell.range.start));
         cell.children
             .iter()
-            .for_each(|child| self.collect_cell_ids(table_id, child, ids));
+            .for_each(|child| self.collect_cell_ids_into(table_id, child, ids));
     }
 
     fn try_insert_symbol(&self, item: &CallHierarchyItem, file: &mut TableNode) -> bool {
@@ -355,6 +538,49 @@ impl GraphGenerator {
     }
 }
 
+/// A directory trie used to build the subgraph (cluster) hierarchy in a single
+/// pass over the file paths.
+#[derive(Default)]
+struct DirTrie {
+    children: BTreeMap<String, DirTrie>,
+    nodes: Vec<String>,
+}
+
+impl DirTrie {
+    /// Turn this node into a cluster titled `title`. Single-child chains that hold
+    /// no files of their own collapse into one cluster (`a/b/c`).
+    fn into_subgraph(mut self, mut title: String) -> Subgraph {
+        while self.nodes.is_empty() && self.children.len() == 1 {
+            let (segment, child) = self.children.into_iter().next().unwrap();
+            title = format!("{}/{}", title, segment);
+            self = child;
+        }
+
+        let subgraphs = self
+            .children
+            .into_iter()
+            .map(|(segment, child)| child.into_subgraph(segment))
+            .collect();
+
+        Subgraph {
+            title,
+            nodes: self.nodes,
+            subgraphs,
+        }
+    }
+}
+
+#[cfg(feature = "persist")]
+fn entries_for<T: Clone>(
+    map: &HashMap<SymbolLocation, Vec<T>>,
+    path: &str,
+) -> Vec<(Position, Vec<T>)> {
+    map.iter()
+        .filter(|(loc, _)| loc.path == path)
+        .map(|(loc, values)| (loc.position, values.clone()))
+        .collect()
+}
+
 trait LocationId {
     fn location_id(&self, files: &HashMap<String, TableNode>) -> Option<(u32, Position)>;
 }
@@ -0,0 +1,78 @@
+use {
+    super::GraphGenerator,
+    crate::graph::{Format, TableNode},
+    std::path::PathBuf,
+};
+
+fn table(id: u32, path: &str) -> TableNode {
+    TableNode {
+        id,
+        path: PathBuf::from(path),
+        cells: vec![],
+    }
+}
+
+#[test]
+fn sibling_dirs_sharing_a_prefix_do_not_nest() {
+    // Regression: prefix matching on directory strings used to nest `app_utils`
+    // under `app` because `"src/app"` is a prefix of `"src/app_utils"`. The trie
+    // keys on whole path segments, so the two stay siblings.
+    let generator = GraphGenerator::new(String::new(), "");
+    let files = [table(1, "src/app/mod.rs"), table(2, "src/app_utils/mod.rs")];
+
+    let subgraphs = generator.subgraphs(files.iter());
+
+    assert_eq!(subgraphs.len(), 1);
+    let src = &subgraphs[0];
+    assert_eq!(src.title, "src");
+
+    let mut titles = src
+        .subgraphs
+        .iter()
+        .map(|s| s.title.as_str())
+        .collect::<Vec<_>>();
+    titles.sort_unstable();
+    assert_eq!(titles, ["app", "app_utils"]);
+
+    // neither cluster swallowed the other's file
+    assert!(src.subgraphs.iter().all(|s| s.subgraphs.is_empty()));
+    let app = src.subgraphs.iter().find(|s| s.title == "app").unwrap();
+    assert_eq!(app.nodes, ["1"]);
+    let app_utils = src.subgraphs.iter().find(|s| s.title == "app_utils").unwrap();
+    assert_eq!(app_utils.nodes, ["2"]);
+}
+
+#[test]
+fn single_child_chains_collapse() {
+    let generator = GraphGenerator::new(String::new(), "");
+    let files = [table(1, "a/b/c/lib.rs")];
+
+    let subgraphs = generator.subgraphs(files.iter());
+
+    assert_eq!(subgraphs.len(), 1);
+    assert_eq!(subgraphs[0].title, "a/b/c");
+    assert_eq!(subgraphs[0].nodes, ["1"]);
+}
+
+#[test]
+fn generate_json_lists_files_and_empty_relations() {
+    let mut generator = GraphGenerator::new(String::new(), "");
+    generator.files.insert("lib.rs".to_string(), table(7, "lib.rs"));
+
+    let json = generator.generate_json();
+
+    assert!(json.contains(r#""id":7"#));
+    assert!(json.contains(r#""path":"lib.rs""#));
+    assert!(json.contains(r#""relations":[]"#));
+    // and it is valid JSON end to end
+    serde_json::from_str::<serde_json::Value>(&json).unwrap();
+}
+
+#[test]
+fn generate_dispatches_on_format() {
+    let mut generator = GraphGenerator::new(String::new(), "");
+    generator.files.insert("lib.rs".to_string(), table(1, "lib.rs"));
+
+    assert!(generator.generate(Format::Dot).contains("digraph"));
+    assert!(generator.generate(Format::Mermaid).contains("flowchart"));
+}
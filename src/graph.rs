@@ -1,20 +1,34 @@
 use {
     enumset::{EnumSet, EnumSetType},
     std::{
+        collections::{HashMap, HashSet},
         hash::{Hash, Hasher},
         path::PathBuf,
     },
 };
 
 pub mod dot;
+pub mod mermaid;
 
-pub trait GenerateSVG {
-    fn generate_svg(
+/// The set of highlighted cells, keyed by file id -> `(line, character)`.
+pub type Highlights = HashMap<u32, HashSet<(u32, u32)>>;
+
+/// Output format selecting which [`Renderer`] serializes the graph.
+#[derive(Debug, Clone, Copy)]
+pub enum Format {
+    Dot,
+    Mermaid,
+}
+
+/// A serialization backend that turns the resolved tables, edges, and clusters
+/// into a textual graph description (Graphviz DOT, Mermaid, ...).
+pub trait Renderer {
+    fn render(
         &self,
-        tables: &[TableNode],
-        // nodes: &[Node],
+        tables: &[&TableNode],
         edges: &[Edge],
         subgraphs: &[Subgraph],
+        highlights: &Highlights,
     ) -> String;
 }
 
@@ -42,6 +56,7 @@ impl PartialEq for Edge {
 impl Eq for Edge {}
 
 #[derive(Debug)]
+#[cfg_attr(feature = "persist", derive(Clone, serde::Serialize, serde::Deserialize))]
 pub struct Cell {
     pub range_start: (u32, u32),
     pub range_end: (u32, u32),
@@ -52,6 +67,7 @@ pub struct Cell {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "persist", derive(Clone, serde::Serialize, serde::Deserialize))]
 pub struct TableNode {
     pub id: u32,
     pub path: PathBuf,
@@ -66,6 +82,7 @@ pub struct Subgraph {
 }
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "persist", derive(Clone, serde::Serialize, serde::Deserialize))]
 pub struct Style {
     pub rounded: bool,
     pub border: Option<u8>,
@@ -75,12 +92,27 @@ pub struct Style {
 #[derive(EnumSetType, Debug)]
 pub enum EdgeCssClass {
     Impl,
+    Inherit,
 }
 
 impl EdgeCssClass {
     pub fn to_str(&self) -> &'static str {
         match self {
             EdgeCssClass::Impl => "impl",
+            EdgeCssClass::Inherit => "inherit",
+        }
+    }
+}
+
+#[derive(EnumSetType, Debug)]
+pub enum CellCssClass {
+    Highlight,
+}
+
+impl CellCssClass {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            CellCssClass::Highlight => "highlight",
         }
     }
 }
@@ -1,13 +1,30 @@
 use {
-    super::EdgeCssClass,
+    super::{CellCssClass, EdgeCssClass},
     crate::{
-        graph::{Cell, Edge, Subgraph, TableNode},
+        graph::{Cell, Edge, Highlights, Renderer, Subgraph, TableNode},
         types::SymbolKind,
     },
     enumset::EnumSet,
     std::iter,
 };
 
+impl Renderer for Dot {
+    fn render(
+        &self,
+        tables: &[&TableNode],
+        edges: &[Edge],
+        subgraphs: &[Subgraph],
+        highlights: &Highlights,
+    ) -> String {
+        Dot::generate_dot_source(
+            tables.iter().copied(),
+            edges.iter().cloned(),
+            subgraphs,
+            highlights,
+        )
+    }
+}
+
 pub(crate) fn escape_html(s: &str) -> String {
     s.replace("&", "&amp;")
         .replace("\"", "&quot;")
@@ -24,6 +41,7 @@ impl Dot {
         // nodes: &[Node],
         edges: E,
         subgraphs: &[Subgraph],
+        highlights: &Highlights,
     ) -> String
     where
         T: Iterator<Item = &'a TableNode>,
@@ -47,7 +65,7 @@ impl Dot {
                     cells = table
                         .cells
                         .iter()
-                        .map(|node| Dot::process_cell(table.id, node))
+                        .map(|node| Dot::process_cell(table.id, node, highlights))
                         .collect::<Vec<_>>()
                         .join("\n"),
                 )
@@ -86,7 +104,16 @@ digraph {{
         )
     }
 
-    fn process_cell(table_id: u32, cell: &Cell) -> String {
+    fn process_cell(table_id: u32, cell: &Cell, highlights: &Highlights) -> String {
+        let mut classes = EnumSet::new();
+        let highlighted = highlights.get(&table_id).is_some_and(|set| {
+            set.contains(&(cell.range.start.line, cell.range.start.character))
+        });
+        if highlighted {
+            classes |= CellCssClass::Highlight;
+        }
+        let cell_classes = Dot::cell_classes(classes);
+
         let styles = [
             cell.style
                 .border
@@ -111,13 +138,13 @@ digraph {{
 
         if cell.children.is_empty() {
             format!(
-                r#"     <TR><TD PORT="{port}" ID="{table_id}:{port}" {styles} {href}>{title}</TD></TR>"#,
+                r#"     <TR><TD PORT="{port}" ID="{table_id}:{port}" {styles} {cell_classes} {href}>{title}</TD></TR>"#,
             )
         } else {
             let (cell_styles, table_styles) = (r#"BORDER="0""#.to_string(), styles);
 
             let dot_cell = format!(
-                r#"     <TR><TD PORT="{port}" {cell_styles} {href}>{title}</TD></TR>"#,
+                r#"     <TR><TD PORT="{port}" {cell_classes} {cell_styles} {href}>{title}</TD></TR>"#,
                 href = EMPTY_STRING,
             );
 
@@ -133,7 +160,7 @@ digraph {{
                     .chain(
                         cell.children
                             .iter()
-                            .map(|item| Dot::process_cell(table_id, item))
+                            .map(|item| Dot::process_cell(table_id, item, highlights))
                     )
                     .collect::<Vec<_>>()
                     .join("\n"),
@@ -185,6 +212,23 @@ digraph {{
             .join("\n")
     }
 
+    fn cell_classes(classes: EnumSet<CellCssClass>) -> String {
+        if classes.is_empty() {
+            return String::new();
+        }
+
+        let names = classes
+            .iter()
+            .map(|c| c.to_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut attrs = format!(r#"class="{names}""#);
+        if classes.contains(CellCssClass::Highlight) {
+            attrs.push_str(r##" BGCOLOR="#fce8b2""##);
+        }
+        attrs
+    }
+
     fn css_classes(classes: EnumSet<EdgeCssClass>) -> String {
         if classes.is_empty() {
             "".to_string()
@@ -0,0 +1,131 @@
+use {
+    super::EdgeCssClass,
+    crate::graph::{Cell, Edge, Highlights, Renderer, Subgraph, TableNode},
+    std::collections::BTreeMap,
+};
+
+/// Renders the graph as a Mermaid `flowchart`, so Crabviz output can be embedded
+/// directly in Markdown and docs environments that render Mermaid but not
+/// Graphviz. Each file becomes a subgraph of its symbol nodes, directory clusters
+/// nest as `subgraph ... end` blocks, calls are drawn with `-->` and
+/// implementation/inheritance edges with `-.->`.
+pub struct Mermaid;
+
+impl Renderer for Mermaid {
+    fn render(
+        &self,
+        tables: &[&TableNode],
+        edges: &[Edge],
+        subgraphs: &[Subgraph],
+        _highlights: &Highlights,
+    ) -> String {
+        let by_id = tables
+            .iter()
+            .map(|table| (table.id, *table))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut out = String::from("flowchart LR\n");
+
+        // directory clusters first, recording which files they consumed
+        let mut clustered = Vec::new();
+        for subgraph in subgraphs {
+            Self::cluster(subgraph, &by_id, &mut clustered, &mut out);
+        }
+
+        // any file not placed in a cluster is rendered at the top level
+        for table in tables {
+            if !clustered.contains(&table.id) {
+                out.push_str(&Self::file_block(table));
+            }
+        }
+
+        for edge in edges {
+            let arrow = if edge.classes.contains(EdgeCssClass::Impl)
+                || edge.classes.contains(EdgeCssClass::Inherit)
+            {
+                "-.->"
+            } else {
+                "-->"
+            };
+            out.push_str(&format!(
+                "    {} {} {}\n",
+                node_id(edge.from),
+                arrow,
+                node_id(edge.to),
+            ));
+        }
+
+        out
+    }
+}
+
+impl Mermaid {
+    fn cluster(
+        subgraph: &Subgraph,
+        by_id: &BTreeMap<u32, &TableNode>,
+        clustered: &mut Vec<u32>,
+        out: &mut String,
+    ) {
+        out.push_str(&format!(
+            "    subgraph {}[\"{}\"]\n",
+            cluster_id(&subgraph.title),
+            escape(&subgraph.title),
+        ));
+
+        for node in &subgraph.nodes {
+            if let Some(table) = node.parse::<u32>().ok().and_then(|id| by_id.get(&id)) {
+                out.push_str(&Self::file_block(table));
+                clustered.push(table.id);
+            }
+        }
+
+        for child in &subgraph.subgraphs {
+            Self::cluster(child, by_id, clustered, out);
+        }
+
+        out.push_str("    end\n");
+    }
+
+    fn file_block(table: &TableNode) -> String {
+        let filename = table.path.file_name().unwrap().to_str().unwrap();
+
+        let mut block = format!("    subgraph f{}[\"{}\"]\n", table.id, escape(filename));
+        for cell in &table.cells {
+            Self::symbol_nodes(table.id, cell, &mut block);
+        }
+        block.push_str("    end\n");
+        block
+    }
+
+    fn symbol_nodes(table_id: u32, cell: &Cell, out: &mut String) {
+        out.push_str(&format!(
+            "        n{}_{}_{}[\"{}\"]\n",
+            table_id,
+            cell.range.start.line,
+            cell.range.start.character,
+            escape(&cell.title),
+        ));
+        for child in &cell.children {
+            Self::symbol_nodes(table_id, child, out);
+        }
+    }
+}
+
+fn node_id(location: (u32, crate::types::Position)) -> String {
+    format!(
+        "n{}_{}_{}",
+        location.0, location.1.line, location.1.character
+    )
+}
+
+fn cluster_id(title: &str) -> String {
+    let sanitized = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    format!("cluster_{sanitized}")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('"', "&quot;")
+}
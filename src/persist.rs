@@ -0,0 +1,101 @@
+//! Content-addressed on-disk cache for incremental regeneration.
+//!
+//! Regenerating a graph re-queries the language server for every file, which is
+//! wasteful when only a handful changed. This cache stores, per file, a digest of
+//! its path plus contents together with the symbols, calls, and interface
+//! implementations that were derived from it. On the next run the client hashes
+//! each file and, for unchanged digests, restores the cached entry instead of
+//! re-querying, so edges wholly between unchanged files are reused verbatim. A
+//! changed file's own entry is not restored, but the incoming calls cached for an
+//! unchanged file are restored as stored, including calls whose caller lies in a
+//! changed file; those are not invalidated.
+
+use {
+    crate::{
+        graph::TableNode,
+        types::{CallHierarchyIncomingCall, CallHierarchyOutgoingCall, Position, SymbolLocation},
+    },
+    data_encoding::BASE32_NOPAD,
+    serde::{Deserialize, Serialize},
+    sha2::{Digest, Sha256},
+    std::{
+        collections::HashMap,
+        fs,
+        io,
+        path::Path,
+    },
+};
+
+/// Everything derived from a single file, keyed by its content-addressed digest.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub table: TableNode,
+    pub incoming_calls: Vec<(Position, Vec<CallHierarchyIncomingCall>)>,
+    pub outgoing_calls: Vec<(Position, Vec<CallHierarchyOutgoingCall>)>,
+    pub interfaces: Vec<(Position, Vec<SymbolLocation>)>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct FileStore {
+    // file path -> content-addressed cache key
+    keys: HashMap<String, String>,
+    // cache key -> derived entry
+    entries: HashMap<String, Entry>,
+}
+
+/// A base32-encoded digest of a file's path and contents, stable across runs.
+pub fn cache_key(path: &str, content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(path.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(content.as_bytes());
+    BASE32_NOPAD.encode(&hasher.finalize())
+}
+
+impl FileStore {
+    pub fn load(db_path: &Path) -> io::Result<Self> {
+        match fs::read(db_path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes).unwrap_or_default()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn flush(&self, db_path: &Path) -> io::Result<()> {
+        let bytes = serde_json::to_vec(self).expect("graph store is serializable");
+        fs::write(db_path, bytes)
+    }
+
+    /// Whether the file at `path` has an entry matching the current `content`.
+    pub fn is_unchanged(&self, path: &str, content: &str) -> bool {
+        self.keys
+            .get(path)
+            .is_some_and(|key| *key == cache_key(path, content))
+    }
+
+    pub fn get(&self, path: &str, content: &str) -> Option<&Entry> {
+        let key = self.keys.get(path)?;
+        (*key == cache_key(path, content))
+            .then(|| self.entries.get(key))
+            .flatten()
+    }
+
+    pub fn insert(&mut self, path: String, content: &str, entry: Entry) {
+        let key = cache_key(&path, content);
+        self.entries.insert(key.clone(), entry);
+        self.keys.insert(path, key);
+    }
+
+    /// A combined digest of every per-file key, so a client can cheaply tell
+    /// whether anything at all changed since the previous run.
+    pub fn digest(&self) -> String {
+        let mut keys = self.keys.values().cloned().collect::<Vec<_>>();
+        keys.sort();
+
+        let mut hasher = Sha256::new();
+        for key in keys {
+            hasher.update(key.as_bytes());
+        }
+        BASE32_NOPAD.encode(&hasher.finalize())
+    }
+}
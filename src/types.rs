@@ -2,7 +2,65 @@ mod lsp;
 
 pub(crate) use lsp::*;
 
+use serde::Serialize;
+
+/// A serializable snapshot of the resolved graph, used by `generate_json` as a
+/// stable machine-readable alternative to the DOT output.
+#[derive(Debug, Serialize)]
+pub struct Graph {
+    pub files: Vec<File>,
+    pub relations: Vec<Relation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct File {
+    pub id: u32,
+    pub path: String,
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub range: Range,
+    pub children: Vec<Symbol>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Relation {
+    pub from: GlobalPosition,
+    pub to: GlobalPosition,
+    pub kind: RelationKind,
+}
+
+#[derive(Debug, Serialize)]
+pub enum RelationKind {
+    Call,
+    Impl,
+    Inherit,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalPosition {
+    pub file_id: u32,
+    pub line: u32,
+    pub character: u32,
+}
+
+impl From<(u32, Position)> for GlobalPosition {
+    fn from((file_id, position): (u32, Position)) -> Self {
+        Self {
+            file_id,
+            line: position.line,
+            character: position.character,
+        }
+    }
+}
+
 #[derive(Hash, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "persist", derive(serde::Serialize, serde::Deserialize))]
 pub struct SymbolLocation {
     pub path: String,
     pub position: Position,